@@ -0,0 +1,184 @@
+use anyhow::Context;
+use chrono::Datelike;
+use clap::Parser;
+use concordium_rust_sdk::{
+    endpoints::{self, QueryError},
+    id::types::{AccountAddress, AccountCredentialWithoutProofs, YearMonth},
+    types::hashes::BlockHash,
+};
+use std::path::PathBuf;
+
+#[derive(clap::Parser, Debug)]
+#[clap(version, author)]
+struct App {
+    #[clap(
+        long = "node",
+        help = "GRPC interface of the node.",
+        default_value = "http://localhost:10000",
+        env = "ACCOUNT_AUDITOR_NODE"
+    )]
+    endpoint:      endpoints::Endpoint,
+    #[clap(
+        long = "rpc-token",
+        help = "GRPC interface access token for accessing the node.",
+        default_value = "rpcadmin",
+        env = "ACCOUNT_AUDITOR_TOKEN"
+    )]
+    token:         String,
+    #[clap(
+        long = "accounts",
+        help = "File with the list of accounts to audit, one address per line. If not given all \
+                accounts that exist at the block are audited.",
+        env = "ACCOUNT_AUDITOR_ACCOUNTS"
+    )]
+    accounts:      Option<PathBuf>,
+    #[clap(
+        long = "block",
+        help = "Block at which to audit the accounts. Defaults to the last finalized block.",
+        env = "ACCOUNT_AUDITOR_BLOCK"
+    )]
+    block:         Option<BlockHash>,
+    #[clap(
+        long = "expiry-months",
+        help = "Flag credentials that expire within this many months.",
+        default_value = "3",
+        env = "ACCOUNT_AUDITOR_EXPIRY_MONTHS"
+    )]
+    expiry_months: u32,
+    #[clap(
+        long = "many-keys",
+        help = "Flag credentials with signature threshold 1 that have at least this many keys.",
+        default_value = "3",
+        env = "ACCOUNT_AUDITOR_MANY_KEYS"
+    )]
+    many_keys:     usize,
+    #[clap(
+        long = "only-flagged",
+        help = "Only report credentials that have at least one flag.",
+        env = "ACCOUNT_AUDITOR_ONLY_FLAGGED"
+    )]
+    only_flagged:  bool,
+}
+
+/// A risky configuration detected on an account or one of its credentials.
+#[derive(Debug, Clone, Copy)]
+enum Flag {
+    /// The credential is past its `validTo` date.
+    Expired,
+    /// The credential expires within the configured number of months.
+    ExpiresSoon,
+    /// The credential has signature threshold 1, but many keys, so any one
+    /// of them can sign on its own.
+    SingleSignatureManyKeys,
+    /// The account threshold is 1, but there are several credentials, so any
+    /// one of them can sign on its own.
+    SingleCredentialThreshold,
+}
+
+impl std::fmt::Display for Flag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Flag::Expired => write!(f, "expired"),
+            Flag::ExpiresSoon => write!(f, "expires-soon"),
+            Flag::SingleSignatureManyKeys => write!(f, "threshold-1-many-keys"),
+            Flag::SingleCredentialThreshold => write!(f, "account-threshold-1-many-credentials"),
+        }
+    }
+}
+
+/// Number of whole months from `now` until the end of the month `valid_to`.
+/// Negative if the credential has already expired.
+fn months_until(now: chrono::DateTime<chrono::Utc>, valid_to: &YearMonth) -> i64 {
+    let now = i64::from(now.year()) * 12 + i64::from(now.month0());
+    let valid_to = i64::from(valid_to.year) * 12 + i64::from(valid_to.month) - 1;
+    valid_to - now
+}
+
+/// Read a list of account addresses, one per line. Empty lines are ignored.
+fn read_accounts(path: &std::path::Path) -> anyhow::Result<Vec<AccountAddress>> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Could not read accounts file {}.", path.display()))?;
+    data.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.parse().with_context(|| format!("Invalid account address {}.", line)))
+        .collect()
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let app = App::parse();
+
+    let mut client = endpoints::Client::connect(app.endpoint, app.token).await?;
+
+    let block = match app.block {
+        Some(block) => block,
+        None => client.get_consensus_status().await?.last_finalized_block,
+    };
+
+    let accounts = match &app.accounts {
+        Some(path) => read_accounts(path)?,
+        None => client.get_account_list(&block).await?,
+    };
+
+    let now = chrono::Utc::now();
+    println!("account,account_threshold,credential_index,threshold,num_keys,valid_to,flags");
+    for account in accounts {
+        let info = match client.get_account_info(&account, &block).await {
+            Ok(info) => info,
+            Err(QueryError::NotFound) => {
+                eprintln!("Account {} does not exist at block {}.", account, block);
+                continue;
+            }
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Could not get account info for {}.", account))
+            }
+        };
+        let account_threshold = u8::from(info.account_threshold);
+        for (index, credential) in info.account_credentials.iter() {
+            let (cred_account, policy) = match &credential.value {
+                AccountCredentialWithoutProofs::Initial {
+                    icdv,
+                } => (&icdv.cred_account, &icdv.policy),
+                AccountCredentialWithoutProofs::Normal {
+                    cdv,
+                    ..
+                } => (&cdv.cred_key_info, &cdv.policy),
+            };
+            let threshold = cred_account.threshold.0;
+            let num_keys = cred_account.keys.len();
+
+            let mut flags = Vec::new();
+            let remaining = months_until(now, &policy.valid_to);
+            if remaining < 0 {
+                flags.push(Flag::Expired);
+            } else if remaining < i64::from(app.expiry_months) {
+                flags.push(Flag::ExpiresSoon);
+            }
+            if threshold == 1 && num_keys >= app.many_keys {
+                flags.push(Flag::SingleSignatureManyKeys);
+            }
+            if account_threshold == 1 && info.account_credentials.len() > 1 {
+                flags.push(Flag::SingleCredentialThreshold);
+            }
+
+            if app.only_flagged && flags.is_empty() {
+                continue;
+            }
+            let flags = flags.iter().map(Flag::to_string).collect::<Vec<_>>().join(";");
+            println!(
+                "{},{},{},{},{},{:04}-{:02},{}",
+                account,
+                account_threshold,
+                index,
+                threshold,
+                num_keys,
+                policy.valid_to.year,
+                policy.valid_to.month,
+                flags
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,619 @@
+use anyhow::{ensure, Context};
+use clap::Parser;
+use concordium_rust_sdk::{
+    endpoints,
+    id::types::AccountAddress,
+    types::{
+        hashes::{BlockHash, TransactionHash},
+        AbsoluteBlockHeight, AccountTransactionDetails, AccountTransactionEffects, Address, Amount,
+        BlockItemSummary, BlockItemSummaryDetails, ContractTraceElement, SpecialTransactionOutcome,
+    },
+};
+use std::{io::Write, path::PathBuf};
+
+#[derive(clap::Parser, Debug)]
+#[clap(arg_required_else_help(true))]
+#[clap(version, author)]
+struct App {
+    #[clap(
+        long = "node",
+        help = "GRPC interface of the node.",
+        default_value = "http://localhost:10000",
+        env = "ACCOUNT_STATEMENT_NODE"
+    )]
+    endpoint:    endpoints::Endpoint,
+    #[clap(
+        long = "rpc-token",
+        help = "GRPC interface access token for accessing the node.",
+        default_value = "rpcadmin",
+        env = "ACCOUNT_STATEMENT_TOKEN"
+    )]
+    token:       String,
+    #[clap(
+        long = "account",
+        help = "Account to produce the statement for.",
+        env = "ACCOUNT_STATEMENT_ACCOUNT"
+    )]
+    account:     AccountAddress,
+    #[clap(
+        long = "from-height",
+        help = "Height of the first block to include.",
+        default_value = "0",
+        env = "ACCOUNT_STATEMENT_FROM_HEIGHT"
+    )]
+    from_height: u64,
+    #[clap(
+        long = "to-height",
+        help = "Height of the last block to include. Defaults to the last finalized block.",
+        env = "ACCOUNT_STATEMENT_TO_HEIGHT"
+    )]
+    to_height:   Option<u64>,
+    #[clap(
+        long = "format",
+        help = "Output format.",
+        default_value = "csv",
+        possible_values = &["csv", "json"],
+        env = "ACCOUNT_STATEMENT_FORMAT"
+    )]
+    format:      String,
+    #[clap(
+        long = "out",
+        help = "File to write the statement to. Defaults to stdout.",
+        env = "ACCOUNT_STATEMENT_OUT"
+    )]
+    out:         Option<PathBuf>,
+}
+
+/// A single line of the statement.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    height:           u64,
+    block:            BlockHash,
+    block_time:       chrono::DateTime<chrono::Utc>,
+    /// The transaction causing the entry. [None] for rewards.
+    transaction:      Option<TransactionHash>,
+    kind:             &'static str,
+    counterparty:     Option<AccountAddress>,
+    /// Change of the public balance of the account, negative if the amount
+    /// left the account. Zero if the effect on the balance is not public,
+    /// e.g., for shielded transfers.
+    amount_micro_ccd: i128,
+    /// Transaction fee paid by the account.
+    fee_micro_ccd:    u64,
+}
+
+/// An entry without the block information.
+struct Effect {
+    transaction:  Option<TransactionHash>,
+    kind:         &'static str,
+    counterparty: Option<AccountAddress>,
+    amount:       i128,
+    fee:          u64,
+}
+
+fn micro(amount: Amount) -> i128 { i128::from(amount.microccd) }
+
+/// Whether the address is the account or one of its aliases.
+fn is_account(address: &Address, account: &AccountAddress) -> bool {
+    matches!(address, Address::Account(a) if a.is_alias(account))
+}
+
+/// The effects of a transaction on the balance of the account.
+fn transaction_effects(account: &AccountAddress, summary: &BlockItemSummary) -> Vec<Effect> {
+    let effect = |kind, counterparty, amount| Effect {
+        transaction: Some(summary.hash),
+        kind,
+        counterparty,
+        amount,
+        fee: 0,
+    };
+    let mut effects = Vec::new();
+    let (sender, cost) = match &summary.details {
+        BlockItemSummaryDetails::AccountTransaction(AccountTransactionDetails {
+            sender,
+            cost,
+            effects: tx_effects,
+        }) => {
+            let is_sender = sender.is_alias(account);
+            match tx_effects {
+                AccountTransactionEffects::AccountTransfer {
+                    amount,
+                    to,
+                }
+                | AccountTransactionEffects::AccountTransferWithMemo {
+                    amount,
+                    to,
+                    ..
+                } => {
+                    if is_sender {
+                        effects.push(effect("transfer-out", Some(*to), -micro(*amount)));
+                    }
+                    if to.is_alias(account) {
+                        effects.push(effect("transfer-in", Some(*sender), micro(*amount)));
+                    }
+                }
+                AccountTransactionEffects::TransferredWithSchedule {
+                    to,
+                    amount,
+                }
+                | AccountTransactionEffects::TransferredWithScheduleAndMemo {
+                    to,
+                    amount,
+                    ..
+                } => {
+                    let total: i128 = amount.iter().map(|(_, a)| micro(*a)).sum();
+                    if is_sender {
+                        effects.push(effect("scheduled-transfer-out", Some(*to), -total));
+                    }
+                    if to.is_alias(account) {
+                        effects.push(effect("scheduled-transfer-in", Some(*sender), total));
+                    }
+                }
+                AccountTransactionEffects::TransferredToEncrypted {
+                    data,
+                } if is_sender => {
+                    effects.push(effect("shield", None, -micro(data.amount)));
+                }
+                AccountTransactionEffects::TransferredToPublic {
+                    amount,
+                    ..
+                } if is_sender => {
+                    effects.push(effect("unshield", None, micro(*amount)));
+                }
+                AccountTransactionEffects::EncryptedAmountTransferred {
+                    added,
+                    ..
+                } => {
+                    if is_sender {
+                        effects.push(effect("shielded-transfer-out", Some(added.receiver), 0));
+                    }
+                    if added.receiver.is_alias(account) {
+                        effects.push(effect("shielded-transfer-in", Some(*sender), 0));
+                    }
+                }
+                AccountTransactionEffects::ContractInitialized {
+                    data,
+                } if is_sender => {
+                    effects.push(effect("contract-init", None, -micro(data.amount)));
+                }
+                AccountTransactionEffects::ContractUpdateIssued {
+                    effects: trace,
+                } => {
+                    for element in trace {
+                        match element {
+                            ContractTraceElement::Updated {
+                                data,
+                            } if is_account(&data.instigator, account) => {
+                                effects.push(effect("contract-update", None, -micro(data.amount)));
+                            }
+                            ContractTraceElement::Transferred {
+                                amount,
+                                to,
+                                ..
+                            } if to.is_alias(account) => {
+                                effects.push(effect("contract-transfer-in", None, micro(*amount)));
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                AccountTransactionEffects::None {
+                    ..
+                } if is_sender => {
+                    effects.push(effect("rejected", None, 0));
+                }
+                _ => {}
+            }
+            (*sender, *cost)
+        }
+        BlockItemSummaryDetails::AccountCreation(details) if details.address.is_alias(account) => {
+            effects.push(effect("account-created", None, 0));
+            return effects;
+        }
+        _ => return effects,
+    };
+    if sender.is_alias(account) {
+        // Transactions without an effect on the balance, e.g., staking
+        // transactions, still cost a fee.
+        if effects.is_empty() {
+            effects.push(effect("transaction", None, 0));
+        }
+        effects[0].fee = cost.microccd;
+    }
+    effects
+}
+
+/// Rewards paid to the account.
+fn reward_effects(account: &AccountAddress, outcome: &SpecialTransactionOutcome) -> Vec<Effect> {
+    let reward = |kind, amount| Effect {
+        transaction: None,
+        kind,
+        counterparty: None,
+        amount,
+        fee: 0,
+    };
+    let mut effects = Vec::new();
+    match outcome {
+        SpecialTransactionOutcome::BakingRewards {
+            baker_rewards,
+            ..
+        } => {
+            for entry in baker_rewards.entries.iter().filter(|e| e.address.is_alias(account)) {
+                effects.push(reward("baking-reward", micro(entry.amount)));
+            }
+        }
+        SpecialTransactionOutcome::FinalizationRewards {
+            finalization_rewards,
+            ..
+        } => {
+            for entry in finalization_rewards.entries.iter().filter(|e| e.address.is_alias(account))
+            {
+                effects.push(reward("finalization-reward", micro(entry.amount)));
+            }
+        }
+        SpecialTransactionOutcome::Mint {
+            foundation_account,
+            mint_platform_development_charge,
+            ..
+        } if foundation_account.is_alias(account) => {
+            effects.push(reward("foundation-reward", micro(*mint_platform_development_charge)));
+        }
+        SpecialTransactionOutcome::BlockReward {
+            baker,
+            baker_reward,
+            foundation_account,
+            foundation_charge,
+            ..
+        } => {
+            if baker.is_alias(account) {
+                effects.push(reward("block-reward", micro(*baker_reward)));
+            }
+            if foundation_account.is_alias(account) {
+                effects.push(reward("foundation-reward", micro(*foundation_charge)));
+            }
+        }
+        SpecialTransactionOutcome::PaydayFoundationReward {
+            foundation_account,
+            development_charge,
+        } if foundation_account.is_alias(account) => {
+            effects.push(reward("foundation-reward", micro(*development_charge)));
+        }
+        SpecialTransactionOutcome::PaydayAccountReward {
+            account: rewarded,
+            transaction_fees,
+            baker_reward,
+            finalization_reward,
+        } if rewarded.is_alias(account) => {
+            let total =
+                micro(*transaction_fees) + micro(*baker_reward) + micro(*finalization_reward);
+            effects.push(reward("payday-reward", total));
+        }
+        _ => {}
+    }
+    effects
+}
+
+/// Number of attempts at processing a block before giving up.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Writes the entries of the statement as they are produced, so that the
+/// entries found so far are kept if processing a later block fails.
+struct StatementWriter {
+    out:     Box<dyn Write>,
+    json:    bool,
+    written: usize,
+}
+
+impl StatementWriter {
+    fn new(mut out: Box<dyn Write>, json: bool) -> std::io::Result<Self> {
+        if json {
+            write!(out, "[")?;
+        } else {
+            writeln!(
+                out,
+                "height,block,block_time,transaction,kind,counterparty,amount_micro_ccd,\
+                 fee_micro_ccd"
+            )?;
+        }
+        Ok(Self {
+            out,
+            json,
+            written: 0,
+        })
+    }
+
+    fn write(&mut self, entry: &Entry) -> anyhow::Result<()> {
+        if self.json {
+            if self.written > 0 {
+                write!(self.out, ",")?;
+            }
+            writeln!(self.out)?;
+            serde_json::to_writer_pretty(&mut self.out, entry)?;
+        } else {
+            writeln!(
+                self.out,
+                "{},{},{},{},{},{},{},{}",
+                entry.height,
+                entry.block,
+                entry.block_time.to_rfc3339(),
+                entry.transaction.map_or_else(String::new, |t| t.to_string()),
+                entry.kind,
+                entry.counterparty.map_or_else(String::new, |a| a.to_string()),
+                entry.amount_micro_ccd,
+                entry.fee_micro_ccd
+            )?;
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Terminate the output. This is also done if the walk fails, so that
+    /// the partial statement is still well-formed.
+    fn finish(mut self) -> std::io::Result<()> {
+        if self.json {
+            writeln!(self.out, "\n]")?;
+        }
+        self.out.flush()
+    }
+}
+
+/// The entries of the statement in the block at the given height.
+async fn block_entries(
+    client: &mut endpoints::Client,
+    account: &AccountAddress,
+    height: u64,
+) -> anyhow::Result<Vec<Entry>> {
+    let block = *client
+        .get_blocks_at_height(AbsoluteBlockHeight::from(height).into())
+        .await?
+        .first()
+        .with_context(|| format!("No block at height {}.", height))?;
+    let summary = client.get_block_summary(&block).await?;
+    let effects: Vec<_> = summary
+        .transaction_summaries()
+        .iter()
+        .filter(|s| s.affected_addresses().iter().any(|a| a.is_alias(account)))
+        .flat_map(|s| transaction_effects(account, s))
+        .chain(summary.special_events().iter().flat_map(|o| reward_effects(account, o)))
+        .collect();
+    if effects.is_empty() {
+        return Ok(Vec::new());
+    }
+    let block_time = client.get_block_info(&block).await?.block_slot_time;
+    Ok(effects
+        .into_iter()
+        .map(|e| Entry {
+            height,
+            block,
+            block_time,
+            transaction: e.transaction,
+            kind: e.kind,
+            counterparty: e.counterparty,
+            amount_micro_ccd: e.amount,
+            fee_micro_ccd: e.fee,
+        })
+        .collect())
+}
+
+/// Write the entries of all blocks in the range, retrying blocks that could
+/// not be processed.
+async fn walk(
+    client: &mut endpoints::Client,
+    account: &AccountAddress,
+    from_height: u64,
+    to_height: u64,
+    writer: &mut StatementWriter,
+) -> anyhow::Result<()> {
+    for height in from_height..=to_height {
+        let mut attempt = 1;
+        let entries = loop {
+            match block_entries(client, account, height).await {
+                Ok(entries) => break entries,
+                Err(e) if attempt < MAX_ATTEMPTS => {
+                    eprintln!("Could not process block at height {}, retrying: {:#}", height, e);
+                    attempt += 1;
+                    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                }
+                Err(e) => {
+                    return Err(e.context(format!(
+                        "Could not process block at height {}. The statement only covers the \
+                         blocks before it.",
+                        height
+                    )))
+                }
+            }
+        };
+        for entry in entries.iter() {
+            writer.write(entry)?;
+        }
+        if !entries.is_empty() {
+            writer.out.flush()?;
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let app = App::parse();
+
+    let mut client = endpoints::Client::connect(app.endpoint, app.token).await?;
+    let last_height = client.get_consensus_status().await?.last_finalized_block_height.height;
+    let to_height = app.to_height.unwrap_or(last_height);
+    ensure!(to_height <= last_height, "Height {} is beyond the last finalized block.", to_height);
+    ensure!(
+        app.from_height <= to_height,
+        "The first height {} is after the last height {}.",
+        app.from_height,
+        to_height
+    );
+
+    let out: Box<dyn Write> = match &app.out {
+        Some(path) => Box::new(std::io::BufWriter::new(
+            std::fs::File::create(path)
+                .with_context(|| format!("Could not create {}.", path.display()))?,
+        )),
+        None => Box::new(std::io::stdout()),
+    };
+    let mut writer = StatementWriter::new(out, app.format == "json")?;
+    let result = walk(&mut client, &app.account, app.from_height, to_height, &mut writer).await;
+    writer.finish()?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use concordium_rust_sdk::{
+        common::types::Timestamp,
+        types::{
+            smart_contracts::{OwnedReceiveName, Parameter, WasmVersion},
+            ContractAddress, Energy, InstanceUpdatedEvent, RejectReason, TransactionIndex,
+        },
+    };
+
+    fn account(byte: u8) -> AccountAddress { AccountAddress([byte; 32]) }
+
+    /// An alias of the address, i.e., an address that only differs in the
+    /// last three bytes.
+    fn alias(address: AccountAddress) -> AccountAddress {
+        let mut bytes = address.0;
+        bytes[31] ^= 1;
+        AccountAddress(bytes)
+    }
+
+    fn ccd(microccd: u64) -> Amount {
+        Amount {
+            microccd,
+        }
+    }
+
+    fn account_transaction(
+        sender: AccountAddress,
+        cost: u64,
+        effects: AccountTransactionEffects,
+    ) -> BlockItemSummary {
+        BlockItemSummary {
+            index:       TransactionIndex {
+                index: 0,
+            },
+            energy_cost: Energy {
+                energy: 500,
+            },
+            hash:        TransactionHash::new([0; 32]),
+            details:     BlockItemSummaryDetails::AccountTransaction(AccountTransactionDetails {
+                cost: ccd(cost),
+                sender,
+                effects,
+            }),
+        }
+    }
+
+    fn summarize(effects: &[Effect]) -> Vec<(&'static str, i128, u64)> {
+        effects.iter().map(|e| (e.kind, e.amount, e.fee)).collect()
+    }
+
+    #[test]
+    fn transfer_to_self() {
+        let me = account(1);
+        let summary = account_transaction(me, 5, AccountTransactionEffects::AccountTransfer {
+            amount: ccd(100),
+            to:     alias(me),
+        });
+        assert_eq!(summarize(&transaction_effects(&me, &summary)), vec![
+            ("transfer-out", -100, 5),
+            ("transfer-in", 100, 0)
+        ]);
+    }
+
+    #[test]
+    fn scheduled_transfer() {
+        let me = account(1);
+        let other = account(2);
+        let summary =
+            account_transaction(other, 5, AccountTransactionEffects::TransferredWithSchedule {
+                to:     me,
+                amount: vec![
+                    (
+                        Timestamp {
+                            millis: 1000,
+                        },
+                        ccd(10),
+                    ),
+                    (
+                        Timestamp {
+                            millis: 2000,
+                        },
+                        ccd(20),
+                    ),
+                ],
+            });
+        assert_eq!(summarize(&transaction_effects(&me, &summary)), vec![(
+            "scheduled-transfer-in",
+            30,
+            0
+        )]);
+        assert_eq!(summarize(&transaction_effects(&other, &summary)), vec![(
+            "scheduled-transfer-out",
+            -30,
+            5
+        )]);
+    }
+
+    #[test]
+    fn rejected_transaction() {
+        let me = account(1);
+        let summary = account_transaction(me, 7, AccountTransactionEffects::None {
+            transaction_type: None,
+            reject_reason:    RejectReason::OutOfEnergy,
+        });
+        assert_eq!(summarize(&transaction_effects(&me, &summary)), vec![("rejected", 0, 7)]);
+        assert!(transaction_effects(&account(2), &summary).is_empty());
+    }
+
+    #[test]
+    fn contract_update_with_transfer_back() {
+        let me = account(1);
+        let contract = ContractAddress::new(0, 0);
+        let summary = account_transaction(me, 5, AccountTransactionEffects::ContractUpdateIssued {
+            effects: vec![
+                ContractTraceElement::Updated {
+                    data: InstanceUpdatedEvent {
+                        contract_version: WasmVersion::V1,
+                        address:          contract,
+                        instigator:       Address::Account(me),
+                        amount:           ccd(50),
+                        message:          Parameter::from(Vec::new()),
+                        receive_name:     OwnedReceiveName::new_unchecked(
+                            "contract.receive".into(),
+                        ),
+                        events:           Vec::new(),
+                    },
+                },
+                ContractTraceElement::Transferred {
+                    from:   contract,
+                    amount: ccd(20),
+                    to:     alias(me),
+                },
+            ],
+        });
+        assert_eq!(summarize(&transaction_effects(&me, &summary)), vec![
+            ("contract-update", -50, 5),
+            ("contract-transfer-in", 20, 0)
+        ]);
+    }
+
+    #[test]
+    fn payday_reward() {
+        let me = account(1);
+        let outcome = SpecialTransactionOutcome::PaydayAccountReward {
+            account:             alias(me),
+            transaction_fees:    ccd(1),
+            baker_reward:        ccd(2),
+            finalization_reward: ccd(3),
+        };
+        assert_eq!(summarize(&reward_effects(&me, &outcome)), vec![("payday-reward", 6, 0)]);
+        assert!(reward_effects(&account(2), &outcome).is_empty());
+    }
+}
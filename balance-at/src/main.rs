@@ -0,0 +1,259 @@
+use anyhow::{bail, Context};
+use clap::Parser;
+use concordium_rust_sdk::{
+    endpoints::{self, QueryError},
+    id::types::AccountAddress,
+    types::{hashes::BlockHash, AbsoluteBlockHeight, AccountStakingInfo, Amount},
+};
+use std::{collections::HashMap, path::PathBuf};
+
+#[derive(clap::Parser, Debug)]
+#[clap(arg_required_else_help(true))]
+#[clap(version, author)]
+struct App {
+    #[clap(
+        long = "node",
+        help = "GRPC interface of the node.",
+        default_value = "http://localhost:10000",
+        env = "BALANCE_AT_NODE"
+    )]
+    endpoint: endpoints::Endpoint,
+    #[clap(
+        long = "rpc-token",
+        help = "GRPC interface access token for accessing the node.",
+        default_value = "rpcadmin",
+        env = "BALANCE_AT_TOKEN"
+    )]
+    token:    String,
+    #[clap(
+        long = "account",
+        help = "Account to query.",
+        required_unless_present = "batch",
+        requires = "at"
+    )]
+    account:  Option<AccountAddress>,
+    #[clap(
+        long = "at",
+        help = "Point in time at which to query the account. Either a block hash, a block height, \
+                a date (YYYY-MM-DD) or a time (RFC3339)."
+    )]
+    at:       Option<BlockPoint>,
+    #[clap(
+        long = "batch",
+        help = "CSV file with lines of the form `account,point` to query in batch.",
+        conflicts_with_all = &["account", "at"]
+    )]
+    batch:    Option<PathBuf>,
+}
+
+/// A point in the chain's history.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum BlockPoint {
+    Hash(BlockHash),
+    Height(u64),
+    /// The last finalized block with slot time no later than the given time.
+    Time(chrono::DateTime<chrono::Utc>),
+}
+
+impl std::str::FromStr for BlockPoint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.len() == 64 {
+            return Ok(BlockPoint::Hash(s.parse()?));
+        }
+        if let Ok(height) = s.parse() {
+            return Ok(BlockPoint::Height(height));
+        }
+        if let Ok(time) = chrono::DateTime::parse_from_rfc3339(s) {
+            return Ok(BlockPoint::Time(time.with_timezone(&chrono::Utc)));
+        }
+        // A plain date denotes the end of that day, so that, e.g., `2022-06-30`
+        // gives the balance at the end of the quarter.
+        let date = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .with_context(|| format!("Could not parse {} as a block hash, height, or time.", s))?;
+        let end_of_day = date.and_hms_milli(23, 59, 59, 999);
+        Ok(BlockPoint::Time(chrono::DateTime::from_utc(end_of_day, chrono::Utc)))
+    }
+}
+
+/// State of an account at a specific block.
+struct Balance {
+    block:      BlockHash,
+    block_time: chrono::DateTime<chrono::Utc>,
+    balance:    Amount,
+    staked:     Amount,
+    locked:     Amount,
+}
+
+/// Resolves points to blocks, caching the result since batches typically
+/// query many accounts at the same point.
+struct Resolver {
+    client:      endpoints::Client,
+    last_height: u64,
+    cache:       HashMap<BlockPoint, BlockHash>,
+}
+
+impl Resolver {
+    async fn new(mut client: endpoints::Client) -> anyhow::Result<Self> {
+        let consensus = client.get_consensus_status().await?;
+        Ok(Self {
+            client,
+            last_height: consensus.last_finalized_block_height.height,
+            cache: HashMap::new(),
+        })
+    }
+
+    async fn block_at_height(&mut self, height: u64) -> anyhow::Result<BlockHash> {
+        if height > self.last_height {
+            bail!("Height {} is beyond the last finalized block.", height);
+        }
+        let blocks =
+            self.client.get_blocks_at_height(AbsoluteBlockHeight::from(height).into()).await?;
+        blocks.first().copied().with_context(|| format!("No block at height {}.", height))
+    }
+
+    async fn slot_time_at_height(
+        &mut self,
+        height: u64,
+    ) -> anyhow::Result<chrono::DateTime<chrono::Utc>> {
+        let block = self.block_at_height(height).await?;
+        Ok(self.client.get_block_info(&block).await?.block_slot_time)
+    }
+
+    /// Find the last finalized block with slot time no later than the given
+    /// time by binary search over block heights.
+    async fn block_at_time(
+        &mut self,
+        time: chrono::DateTime<chrono::Utc>,
+    ) -> anyhow::Result<BlockHash> {
+        if self.slot_time_at_height(0).await? > time {
+            bail!("{} is before genesis.", time);
+        }
+        if self.slot_time_at_height(self.last_height).await? < time {
+            bail!("{} is after the last finalized block.", time);
+        }
+        let mut lo = 0;
+        let mut hi = self.last_height;
+        while lo < hi {
+            let mid = lo + (hi - lo + 1) / 2;
+            if self.slot_time_at_height(mid).await? <= time {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        self.block_at_height(lo).await
+    }
+
+    async fn resolve(&mut self, point: &BlockPoint) -> anyhow::Result<BlockHash> {
+        if let Some(block) = self.cache.get(point) {
+            return Ok(*block);
+        }
+        let block = match point {
+            BlockPoint::Hash(block) => *block,
+            BlockPoint::Height(height) => self.block_at_height(*height).await?,
+            BlockPoint::Time(time) => self.block_at_time(*time).await?,
+        };
+        self.cache.insert(point.clone(), block);
+        Ok(block)
+    }
+
+    /// The balance of the account at the point. An account that does not
+    /// exist at the point has zero balance.
+    async fn balance_at(
+        &mut self,
+        account: &AccountAddress,
+        point: &BlockPoint,
+    ) -> anyhow::Result<Balance> {
+        let block = self.resolve(point).await?;
+        let block_time = self.client.get_block_info(&block).await?.block_slot_time;
+        let zero = Amount {
+            microccd: 0,
+        };
+        let info = match self.client.get_account_info(account, &block).await {
+            Ok(info) => info,
+            Err(QueryError::NotFound) => {
+                return Ok(Balance {
+                    block,
+                    block_time,
+                    balance: zero,
+                    staked: zero,
+                    locked: zero,
+                })
+            }
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Could not get account info for {} in block {}.", account, block)
+                })
+            }
+        };
+        let staked = match info.account_stake {
+            Some(AccountStakingInfo::Baker {
+                staked_amount,
+                ..
+            }) => staked_amount,
+            Some(AccountStakingInfo::Delegated {
+                staked_amount,
+                ..
+            }) => staked_amount,
+            None => zero,
+        };
+        Ok(Balance {
+            block,
+            block_time,
+            balance: info.account_amount,
+            staked,
+            locked: info.account_release_schedule.total,
+        })
+    }
+}
+
+/// Read the `account,point` pairs of a batch file. Empty lines are ignored.
+fn read_batch(path: &std::path::Path) -> anyhow::Result<Vec<(AccountAddress, BlockPoint)>> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Could not read batch file {}.", path.display()))?;
+    data.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (account, point) =
+                line.split_once(',').with_context(|| format!("Invalid line {}.", line))?;
+            let account = account
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid account address {}.", account))?;
+            Ok((account, point.parse()?))
+        })
+        .collect()
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let app = App::parse();
+
+    let queries = match (&app.batch, app.account, app.at) {
+        (Some(path), _, _) => read_batch(path)?,
+        (None, Some(account), Some(at)) => vec![(account, at)],
+        _ => bail!("Either --batch or both --account and --at must be given."),
+    };
+
+    let client = endpoints::Client::connect(app.endpoint, app.token).await?;
+    let mut resolver = Resolver::new(client).await?;
+
+    println!("account,block,block_time,balance,staked,locked");
+    for (account, point) in queries {
+        let balance = resolver.balance_at(&account, &point).await?;
+        println!(
+            "{},{},{},{},{},{}",
+            account,
+            balance.block,
+            balance.block_time.to_rfc3339(),
+            balance.balance,
+            balance.staked,
+            balance.locked
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use anyhow::{bail, ensure, Context};
+use clap::Parser;
+use concordium_rust_sdk::{
+    common::{types::TransactionTime, Versioned},
+    endpoints::{self, QueryError},
+    id::{
+        account_holder::create_credential,
+        constants::{ArCurve, AttributeKind, IpPairing},
+        types::{
+            account_address_from_registration_id, AccountCredential, AccountCredentialMessage,
+            AccountKeys, CredentialData, IdObjectUseData, IdentityObject, IpContext, IpIdentity,
+            KeyIndex, KeyPair, Policy, SignatureThreshold, SystemAttributeRandomness,
+        },
+    },
+    types::{
+        hashes::TransactionHash,
+        transactions::{send, BlockItem, EncodedPayload},
+        Amount, TransactionStatus, WalletAccount,
+    },
+};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{collections::BTreeMap, io::Write, path::PathBuf};
+
+#[derive(clap::Parser, Debug)]
+#[clap(arg_required_else_help(true))]
+#[clap(version, author)]
+struct App {
+    #[clap(
+        long = "node",
+        help = "GRPC interface of the node.",
+        default_value = "http://localhost:10000",
+        env = "BULK_ACCOUNT_CREATOR_NODE"
+    )]
+    endpoint:    endpoints::Endpoint,
+    #[clap(
+        long = "rpc-token",
+        help = "GRPC interface access token for accessing the node.",
+        default_value = "rpcadmin",
+        env = "BULK_ACCOUNT_CREATOR_TOKEN"
+    )]
+    token:       String,
+    #[clap(
+        long = "id-object",
+        help = "File with the identity object.",
+        env = "BULK_ACCOUNT_CREATOR_ID_OBJECT"
+    )]
+    id_object:   PathBuf,
+    #[clap(
+        long = "id-use-data",
+        help = "File with the private identity object use data.",
+        env = "BULK_ACCOUNT_CREATOR_ID_USE_DATA"
+    )]
+    id_use_data: PathBuf,
+    #[clap(
+        long = "ip",
+        help = "Identity of the identity provider that issued the identity object.",
+        env = "BULK_ACCOUNT_CREATOR_IP"
+    )]
+    ip:          u32,
+    #[clap(
+        long = "count",
+        help = "Number of accounts to create. Indices for which the account already exists do not \
+                count.",
+        env = "BULK_ACCOUNT_CREATOR_COUNT"
+    )]
+    count:       u8,
+    #[clap(
+        long = "start-index",
+        help = "Credential index of the first account to create. Accounts are created with \
+                consecutive indices from this one, skipping indices for which the account already \
+                exists. Index 0 is the initial account created by the identity provider.",
+        default_value = "1",
+        env = "BULK_ACCOUNT_CREATOR_START_INDEX"
+    )]
+    start_index: u8,
+    #[clap(
+        long = "out",
+        help = "Directory to write the key files of the created accounts to.",
+        env = "BULK_ACCOUNT_CREATOR_OUT"
+    )]
+    out:         PathBuf,
+    #[clap(
+        long = "faucet",
+        help = "Key file of an account used to fund each created account.",
+        requires = "fund-amount",
+        env = "BULK_ACCOUNT_CREATOR_FAUCET"
+    )]
+    faucet:      Option<PathBuf>,
+    #[clap(
+        long = "fund-amount",
+        help = "Amount of CCD to transfer from the faucet to each created account.",
+        env = "BULK_ACCOUNT_CREATOR_FUND_AMOUNT"
+    )]
+    fund_amount: Option<Amount>,
+    #[clap(
+        long = "expiry",
+        help = "Number of seconds after which submitted transactions expire.",
+        default_value = "300",
+        env = "BULK_ACCOUNT_CREATOR_EXPIRY"
+    )]
+    expiry:      u64,
+}
+
+fn read_json<A: serde::de::DeserializeOwned>(path: &std::path::Path) -> anyhow::Result<A> {
+    let data =
+        std::fs::read(path).with_context(|| format!("Could not read {}.", path.display()))?;
+    serde_json::from_slice(&data).with_context(|| format!("Could not parse {}.", path.display()))
+}
+
+/// Write the file, failing if it already exists. On unix the file is only
+/// accessible by the owner since it contains private keys.
+fn write_new(path: &std::path::Path, data: &[u8]) -> anyhow::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file =
+        options.open(path).with_context(|| format!("Could not create {}.", path.display()))?;
+    file.write_all(data).with_context(|| format!("Could not write {}.", path.display()))
+}
+
+fn expiry_after(seconds: u64) -> TransactionTime {
+    TransactionTime::from_seconds(chrono::Utc::now().timestamp() as u64 + seconds)
+}
+
+/// Wait until the transaction is finalized and fail if it was rejected.
+async fn wait_until_finalized(
+    client: &mut endpoints::Client,
+    hash: &TransactionHash,
+) -> anyhow::Result<()> {
+    loop {
+        if let TransactionStatus::Finalized(outcomes) = client.get_transaction_status(hash).await? {
+            let (_, summary) =
+                outcomes.iter().next().context("Finalized transaction without outcome.")?;
+            if let Some(reason) = summary.is_rejected_account_transaction() {
+                bail!("Transaction {} was rejected: {:?}", hash, reason);
+            }
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let app = App::parse();
+
+    let id_object: Versioned<IdentityObject<IpPairing, ArCurve, AttributeKind>> =
+        read_json(&app.id_object)?;
+    let id_object = id_object.value;
+    let id_use_data: Versioned<IdObjectUseData<IpPairing, ArCurve>> = read_json(&app.id_use_data)?;
+    let id_use_data = id_use_data.value;
+    let faucet = app
+        .faucet
+        .as_ref()
+        .map(|path| WalletAccount::from_json_file(path))
+        .transpose()
+        .context("Could not read the faucet keys.")?;
+
+    let max_accounts = id_object.alist.max_accounts;
+    let end_index = u16::from(app.start_index) + u16::from(app.count);
+    ensure!(
+        end_index <= u16::from(max_accounts),
+        "The identity allows at most {} accounts, but accounts up to index {} were requested.",
+        max_accounts,
+        end_index - 1
+    );
+
+    std::fs::create_dir_all(&app.out)
+        .with_context(|| format!("Could not create {}.", app.out.display()))?;
+
+    let mut client = endpoints::Client::connect(app.endpoint, app.token).await?;
+    let block = client.get_consensus_status().await?.last_finalized_block;
+    let global_context = client.get_cryptographic_parameters(&block).await?;
+    let ip_identity = IpIdentity(app.ip);
+    let ip_info = client
+        .get_identity_providers(&block)
+        .await?
+        .into_iter()
+        .find(|ip| ip.ip_identity == ip_identity)
+        .with_context(|| format!("Identity provider {} does not exist.", ip_identity))?;
+    let ars_infos = client
+        .get_anonymity_revokers(&block)
+        .await?
+        .into_iter()
+        .map(|ar| (ar.ar_identity, ar))
+        .collect::<BTreeMap<_, _>>();
+    let context = IpContext::new(&ip_info, &ars_infos, &global_context);
+
+    let mut csprng = rand::thread_rng();
+    let mut created = 0;
+    for index in app.start_index..max_accounts {
+        if created == app.count {
+            break;
+        }
+        let cred_data = CredentialData {
+            keys:      [(KeyIndex(0), KeyPair::generate(&mut csprng))].into_iter().collect(),
+            threshold: SignatureThreshold(1),
+        };
+        // Do not reveal any attributes.
+        let policy = Policy {
+            valid_to:   id_object.alist.valid_to,
+            created_at: id_object.alist.created_at,
+            policy_vec: BTreeMap::new(),
+            _phantom:   Default::default(),
+        };
+        let expiry = expiry_after(app.expiry);
+        let (cdi, _) = create_credential(
+            context,
+            &id_object,
+            &id_use_data,
+            index,
+            policy,
+            &cred_data,
+            &SystemAttributeRandomness,
+            &either::Left(expiry),
+        )
+        .with_context(|| format!("Could not create credential {}.", index))?;
+        let address = account_address_from_registration_id(&cdi.values.cred_id);
+        let block = client.get_consensus_status().await?.last_finalized_block;
+        match client.get_account_info(&address, &block).await {
+            Ok(_) => {
+                println!("Account {} with index {} already exists, skipping.", address, index);
+                continue;
+            }
+            Err(QueryError::NotFound) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        // Write the keys before deploying the credential so that they are not
+        // lost if the credential is deployed, but the tool fails before
+        // observing that.
+        let account = WalletAccount {
+            address,
+            keys: AccountKeys::from(cred_data),
+        };
+        let key_file = app.out.join(format!("{}.json", address));
+        let pending_file = app.out.join(format!("{}.json.pending", address));
+        write_new(&pending_file, &serde_json::to_vec_pretty(&account)?)?;
+        let failed = || {
+            format!(
+                "Could not create account {} with index {}. Its keys are in {}.",
+                address,
+                index,
+                pending_file.display()
+            )
+        };
+
+        let block_item =
+            BlockItem::<EncodedPayload>::CredentialDeployment(Box::new(AccountCredentialMessage {
+                message_expiry: expiry,
+                credential:     AccountCredential::Normal {
+                    cdi,
+                },
+            }));
+        let hash = client.send_block_item(&block_item).await.with_context(failed)?;
+        wait_until_finalized(&mut client, &hash).await.with_context(failed)?;
+        std::fs::rename(&pending_file, &key_file)
+            .with_context(|| format!("Could not write {}.", key_file.display()))?;
+        println!("Created account {} with index {} in transaction {}.", address, index, hash);
+        created += 1;
+
+        if let (Some(faucet), Some(amount)) = (&faucet, app.fund_amount) {
+            let nonce = client.get_next_account_nonce(&faucet.address).await?.nonce;
+            let transfer = send::transfer(
+                &faucet.keys,
+                faucet.address,
+                nonce,
+                expiry_after(app.expiry),
+                address,
+                amount,
+            );
+            let hash = client.send_block_item(&BlockItem::AccountTransaction(transfer)).await?;
+            wait_until_finalized(&mut client, &hash)
+                .await
+                .with_context(|| format!("Could not fund account {}.", address))?;
+            println!("Funded account {} with {} CCD in transaction {}.", address, amount, hash);
+        }
+    }
+    ensure!(
+        created == app.count,
+        "Only {} of {} accounts were created since the identity allows at most {} accounts.",
+        created,
+        app.count,
+        max_accounts
+    );
+    Ok(())
+}
@@ -0,0 +1,229 @@
+use anyhow::{ensure, Context};
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use clap::Parser;
+use concordium_rust_sdk::{
+    endpoints::{self, QueryError},
+    id::types::AccountAddress,
+    types::{
+        hashes::{BlockHash, TransactionHash},
+        TransactionStatus,
+    },
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::SocketAddr,
+    sync::Arc,
+};
+
+#[derive(clap::Parser, Debug)]
+#[clap(arg_required_else_help(true))]
+#[clap(version, author)]
+struct App {
+    #[clap(
+        long = "network",
+        help = "Network to query, given as `name=url` where `url` is the GRPC interface of a node \
+                on the network, e.g., `mainnet=http://localhost:10000`. Can be given multiple \
+                times.",
+        required = true,
+        use_value_delimiter = true,
+        env = "NETWORK_LOOKUP_NETWORKS"
+    )]
+    networks:       Vec<Network>,
+    #[clap(
+        long = "rpc-token",
+        help = "GRPC interface access token for accessing the nodes.",
+        default_value = "rpcadmin",
+        env = "NETWORK_LOOKUP_TOKEN"
+    )]
+    token:          String,
+    #[clap(
+        long = "listen-address",
+        help = "Address on which to serve the API.",
+        default_value = "0.0.0.0:8080",
+        env = "NETWORK_LOOKUP_LISTEN_ADDRESS"
+    )]
+    listen_address: SocketAddr,
+}
+
+/// A named network together with the node used to query it.
+#[derive(Debug, Clone)]
+struct Network {
+    name:     String,
+    endpoint: endpoints::Endpoint,
+}
+
+impl std::str::FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, url) =
+            s.split_once('=').context("Networks must be given in the form `name=url`.")?;
+        let name = name.trim();
+        ensure!(!name.is_empty(), "Network names must not be empty.");
+        Ok(Self {
+            name:     name.to_string(),
+            endpoint: url.trim().parse().with_context(|| format!("Invalid node URL {}.", url))?,
+        })
+    }
+}
+
+/// Outcome of looking up an entity on a single network.
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+enum Lookup<Info> {
+    Found {
+        info: Info,
+    },
+    NotFound,
+    Error {
+        error: String,
+    },
+}
+
+impl<Info> From<anyhow::Result<Option<Info>>> for Lookup<Info> {
+    fn from(result: anyhow::Result<Option<Info>>) -> Self {
+        match result {
+            Ok(Some(info)) => Lookup::Found {
+                info,
+            },
+            Ok(None) => Lookup::NotFound,
+            Err(e) => Lookup::Error {
+                error: format!("{:#}", e),
+            },
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountSummary {
+    /// Block in which the account was queried.
+    block:         BlockHash,
+    account_index: u64,
+    balance:       String,
+    nonce:         u64,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionSummary {
+    /// One of `received`, `committed` or `finalized`.
+    status: &'static str,
+    /// Blocks the transaction is in. Empty if only received.
+    blocks: Vec<BlockHash>,
+    sender: Option<AccountAddress>,
+}
+
+struct State {
+    networks: Vec<Network>,
+    token:    String,
+}
+
+impl State {
+    async fn connect(&self, network: &Network) -> anyhow::Result<endpoints::Client> {
+        let endpoint = network
+            .endpoint
+            .clone()
+            .connect_timeout(std::time::Duration::from_secs(2))
+            .timeout(std::time::Duration::from_secs(5));
+        endpoints::Client::connect(endpoint, self.token.clone())
+            .await
+            .with_context(|| format!("Could not connect to the node for {}.", network.name))
+    }
+
+    async fn lookup_account(
+        &self,
+        network: &Network,
+        address: &AccountAddress,
+    ) -> anyhow::Result<Option<AccountSummary>> {
+        let mut client = self.connect(network).await?;
+        let block = client.get_consensus_status().await?.last_finalized_block;
+        match client.get_account_info(address, &block).await {
+            Ok(info) => Ok(Some(AccountSummary {
+                block,
+                account_index: info.account_index.index,
+                balance: info.account_amount.to_string(),
+                nonce: info.account_nonce.nonce,
+            })),
+            Err(QueryError::NotFound) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn lookup_transaction(
+        &self,
+        network: &Network,
+        hash: &TransactionHash,
+    ) -> anyhow::Result<Option<TransactionSummary>> {
+        let mut client = self.connect(network).await?;
+        let (status, outcomes) = match client.get_transaction_status(hash).await {
+            Ok(TransactionStatus::Received) => ("received", BTreeMap::new()),
+            Ok(TransactionStatus::Committed(outcomes)) => ("committed", outcomes),
+            Ok(TransactionStatus::Finalized(outcomes)) => ("finalized", outcomes),
+            Err(QueryError::NotFound) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Some(TransactionSummary {
+            status,
+            blocks: outcomes.keys().copied().collect(),
+            sender: outcomes.values().next().and_then(|summary| summary.sender_account()),
+        }))
+    }
+}
+
+/// Look up the account on all networks in parallel.
+async fn account(
+    Path(address): Path<String>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<Json<BTreeMap<String, Lookup<AccountSummary>>>, (StatusCode, String)> {
+    let address: AccountAddress = address
+        .parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid account address.".to_string()))?;
+    let lookups = state.networks.iter().map(|network| async {
+        (network.name.clone(), state.lookup_account(network, &address).await.into())
+    });
+    Ok(Json(futures::future::join_all(lookups).await.into_iter().collect()))
+}
+
+/// Look up the transaction on all networks in parallel.
+async fn transaction(
+    Path(hash): Path<String>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<Json<BTreeMap<String, Lookup<TransactionSummary>>>, (StatusCode, String)> {
+    let hash: TransactionHash = hash
+        .parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid transaction hash.".to_string()))?;
+    let lookups = state.networks.iter().map(|network| async {
+        (network.name.clone(), state.lookup_transaction(network, &hash).await.into())
+    });
+    Ok(Json(futures::future::join_all(lookups).await.into_iter().collect()))
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let app = App::parse();
+
+    // Results are keyed by network name, so names must be unique.
+    let mut names = BTreeSet::new();
+    for network in app.networks.iter() {
+        ensure!(names.insert(&network.name), "Network {} is given more than once.", network.name);
+    }
+
+    let state = Arc::new(State {
+        networks: app.networks,
+        token:    app.token,
+    });
+
+    let router = Router::new()
+        .route("/accounts/:address", get(account))
+        .route("/transactions/:hash", get(transaction))
+        .layer(Extension(state));
+
+    axum::Server::bind(&app.listen_address).serve(router.into_make_service()).await?;
+    Ok(())
+}
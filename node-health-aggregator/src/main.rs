@@ -0,0 +1,294 @@
+use anyhow::{ensure, Context};
+use axum::{extract::Extension, http::StatusCode, routing::get, Json, Router};
+use clap::Parser;
+use concordium_rust_sdk::{
+    endpoints,
+    types::{hashes::BlockHash, AbsoluteBlockHeight},
+};
+use prometheus::{Encoder, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+use std::{collections::BTreeSet, net::SocketAddr, num::NonZeroU64, sync::Arc};
+use tokio::sync::RwLock;
+
+#[derive(clap::Parser, Debug)]
+#[clap(arg_required_else_help(true))]
+#[clap(version, author)]
+struct App {
+    #[clap(
+        long = "node",
+        help = "GRPC interface of a node to monitor. Can be given multiple times.",
+        required = true,
+        use_value_delimiter = true,
+        env = "NODE_HEALTH_AGGREGATOR_NODES"
+    )]
+    nodes:          Vec<endpoints::Endpoint>,
+    #[clap(
+        long = "rpc-token",
+        help = "GRPC interface access token for accessing the nodes.",
+        default_value = "rpcadmin",
+        env = "NODE_HEALTH_AGGREGATOR_TOKEN"
+    )]
+    token:          String,
+    #[clap(
+        long = "listen-address",
+        help = "Address on which to serve the status and metrics.",
+        default_value = "0.0.0.0:8080",
+        env = "NODE_HEALTH_AGGREGATOR_LISTEN_ADDRESS"
+    )]
+    listen_address: SocketAddr,
+    #[clap(
+        long = "max-lag",
+        help = "Number of blocks a node's last finalized block may be behind the most advanced \
+                node before it is reported as lagging.",
+        default_value = "10",
+        env = "NODE_HEALTH_AGGREGATOR_MAX_LAG"
+    )]
+    max_lag:        u64,
+    #[clap(
+        long = "poll-interval",
+        help = "Number of seconds between polls of the nodes.",
+        default_value = "10",
+        env = "NODE_HEALTH_AGGREGATOR_POLL_INTERVAL"
+    )]
+    poll_interval:  NonZeroU64,
+}
+
+/// The state of a single node as observed in a poll.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NodeStatus {
+    node:                  String,
+    finalized_height:      Option<u64>,
+    finalized_hash:        Option<BlockHash>,
+    /// The node's finalized block at the lowest finalized height of all the
+    /// responding nodes, used to detect forks.
+    hash_at_common_height: Option<BlockHash>,
+    /// Number of blocks the node is behind the most advanced node.
+    lag:                   Option<u64>,
+    lagging:               bool,
+    /// Set if the node did not respond.
+    error:                 Option<String>,
+    /// Set if the node responded, but its block at the common height could
+    /// not be looked up.
+    fork_check_error:      Option<String>,
+}
+
+/// The combined state of all the nodes as observed in a poll.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Status {
+    /// Time of the poll, [None] until the first poll has completed.
+    checked_at:    Option<chrono::DateTime<chrono::Utc>>,
+    common_height: Option<u64>,
+    /// Whether the responding nodes disagree about the finalized block at the
+    /// common height.
+    forked:        bool,
+    nodes:         Vec<NodeStatus>,
+}
+
+struct Metrics {
+    registry:         Registry,
+    up:               IntGaugeVec,
+    finalized_height: IntGaugeVec,
+    lag:              IntGaugeVec,
+    forked:           IntGauge,
+}
+
+impl Metrics {
+    fn new() -> anyhow::Result<Self> {
+        let registry = Registry::new();
+        let up = IntGaugeVec::new(
+            Opts::new("node_health_up", "Whether the node responded in the last poll."),
+            &["node"],
+        )?;
+        let finalized_height = IntGaugeVec::new(
+            Opts::new("node_health_finalized_height", "Height of the node's last finalized block."),
+            &["node"],
+        )?;
+        let lag = IntGaugeVec::new(
+            Opts::new(
+                "node_health_lag_blocks",
+                "Number of blocks the node is behind the most advanced node.",
+            ),
+            &["node"],
+        )?;
+        let forked = IntGauge::new(
+            "node_health_forked",
+            "1 if the nodes disagree about the finalized block at a common height, 0 otherwise.",
+        )?;
+        registry.register(Box::new(up.clone()))?;
+        registry.register(Box::new(finalized_height.clone()))?;
+        registry.register(Box::new(lag.clone()))?;
+        registry.register(Box::new(forked.clone()))?;
+        Ok(Self {
+            registry,
+            up,
+            finalized_height,
+            lag,
+            forked,
+        })
+    }
+
+    fn update(&self, status: &Status) {
+        for node in status.nodes.iter() {
+            let labels = [node.node.as_str()];
+            self.up.with_label_values(&labels).set(node.error.is_none().into());
+            // Values of unresponsive nodes are removed rather than left at
+            // their last known values. Removal fails if there is no value,
+            // which is fine.
+            match node.finalized_height {
+                Some(height) => self.finalized_height.with_label_values(&labels).set(height as i64),
+                None => {
+                    let _ = self.finalized_height.remove_label_values(&labels);
+                }
+            }
+            match node.lag {
+                Some(lag) => self.lag.with_label_values(&labels).set(lag as i64),
+                None => {
+                    let _ = self.lag.remove_label_values(&labels);
+                }
+            }
+        }
+        self.forked.set(status.forked.into());
+    }
+}
+
+struct State {
+    status:  RwLock<Status>,
+    metrics: Metrics,
+}
+
+async fn connect(endpoint: &endpoints::Endpoint, token: &str) -> anyhow::Result<endpoints::Client> {
+    let endpoint = endpoint
+        .clone()
+        .connect_timeout(std::time::Duration::from_secs(2))
+        .timeout(std::time::Duration::from_secs(5));
+    Ok(endpoints::Client::connect(endpoint, token.to_string()).await?)
+}
+
+async fn finalized_block_at(
+    client: &mut endpoints::Client,
+    height: u64,
+) -> anyhow::Result<BlockHash> {
+    let blocks = client.get_blocks_at_height(AbsoluteBlockHeight::from(height).into()).await?;
+    blocks.first().copied().with_context(|| format!("No block at height {}.", height))
+}
+
+/// Query all nodes in parallel and compare their last finalized blocks.
+async fn poll_nodes(nodes: &[endpoints::Endpoint], token: &str, max_lag: u64) -> Status {
+    let heads = futures::future::join_all(nodes.iter().map(|node| async move {
+        let mut client = connect(node, token).await?;
+        let consensus = client.get_consensus_status().await?;
+        Ok::<_, anyhow::Error>((
+            client,
+            consensus.last_finalized_block_height.height,
+            consensus.last_finalized_block,
+        ))
+    }))
+    .await;
+
+    let heights = heads.iter().filter_map(|head| head.as_ref().ok().map(|(_, height, _)| *height));
+    let common_height = heights.clone().min();
+    let max_height = heights.max();
+
+    let nodes = futures::future::join_all(nodes.iter().zip(heads).map(|(node, head)| async move {
+        let node = node.uri().to_string();
+        let (mut client, height, hash) = match head {
+            Ok(head) => head,
+            Err(e) => {
+                return NodeStatus {
+                    node,
+                    finalized_height: None,
+                    finalized_hash: None,
+                    hash_at_common_height: None,
+                    lag: None,
+                    lagging: false,
+                    error: Some(format!("{:#}", e)),
+                    fork_check_error: None,
+                }
+            }
+        };
+        let lag = max_height.map(|max| max - height);
+        let (hash_at_common_height, fork_check_error) = match common_height {
+            Some(common) => match finalized_block_at(&mut client, common).await {
+                Ok(hash) => (Some(hash), None),
+                Err(e) => (None, Some(format!("{:#}", e))),
+            },
+            None => (None, None),
+        };
+        NodeStatus {
+            node,
+            finalized_height: Some(height),
+            finalized_hash: Some(hash),
+            hash_at_common_height,
+            lag,
+            lagging: lag.map_or(false, |lag| lag > max_lag),
+            error: None,
+            fork_check_error,
+        }
+    }))
+    .await;
+
+    let mut common_hashes = nodes.iter().filter_map(|node| node.hash_at_common_height);
+    let forked = match common_hashes.next() {
+        Some(first) => common_hashes.any(|hash| hash != first),
+        None => false,
+    };
+
+    Status {
+        checked_at: Some(chrono::Utc::now()),
+        common_height,
+        forked,
+        nodes,
+    }
+}
+
+async fn poll(app: App, state: Arc<State>) {
+    let mut interval =
+        tokio::time::interval(std::time::Duration::from_secs(app.poll_interval.get()));
+    loop {
+        interval.tick().await;
+        let status = poll_nodes(&app.nodes, &app.token, app.max_lag).await;
+        state.metrics.update(&status);
+        *state.status.write().await = status;
+    }
+}
+
+async fn status(Extension(state): Extension<Arc<State>>) -> Json<Status> {
+    Json(state.status.read().await.clone())
+}
+
+async fn metrics(Extension(state): Extension<Arc<State>>) -> Result<Vec<u8>, StatusCode> {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&state.metrics.registry.gather(), &mut buffer)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(buffer)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let app = App::parse();
+
+    // Nodes are identified by their URI in the status and the metrics.
+    let mut uris = BTreeSet::new();
+    for node in app.nodes.iter() {
+        let uri = node.uri().to_string();
+        ensure!(uris.insert(uri.clone()), "Node {} is given more than once.", uri);
+    }
+
+    let state = Arc::new(State {
+        status:  RwLock::new(Status::default()),
+        metrics: Metrics::new()?,
+    });
+
+    let listen_address = app.listen_address;
+    tokio::spawn(poll(app, state.clone()));
+
+    let router = Router::new()
+        .route("/status", get(status))
+        .route("/metrics", get(metrics))
+        .layer(Extension(state));
+
+    axum::Server::bind(&listen_address).serve(router.into_make_service()).await?;
+    Ok(())
+}
@@ -1,9 +1,15 @@
+use axum::{extract::Extension, http::StatusCode, routing::get, Json, Router};
 use clap::Parser;
 use concordium_rust_sdk::{
     endpoints::{self, QueryError, RPCError},
-    types::queries::{ActiveConsensusState, ConsensusState},
+    types::{
+        network::PeerElementCatchupStatus,
+        queries::{ActiveConsensusState, ConsensusState},
+    },
 };
+use std::{net::SocketAddr, num::NonZeroU64, sync::Arc};
 use thiserror::Error;
+use tokio::sync::RwLock;
 
 #[derive(clap::Parser, Debug)]
 #[clap(arg_required_else_help(true))]
@@ -15,32 +21,59 @@ struct App {
         default_value = "http://localhost:10000",
         env = "LIVENESS_CHECKER_NODE"
     )]
-    endpoint:      endpoints::Endpoint,
+    endpoint:          endpoints::Endpoint,
     #[clap(
         long = "rpc-token",
         help = "GRPC interface access token for accessing the node.",
         default_value = "rpcadmin",
         env = "LIVENESS_CHECKER_TOKEN"
     )]
-    token:         String,
+    token:             String,
     #[clap(
         long = "max-finalized-behind",
         help = "Maximum number of seconds the last finalized block can be behind present.",
         env = "LIVENESS_CHECKER_MAX_FINALIZED_BEHIND"
     )]
-    max_behind:    i64,
+    max_behind:        i64,
     #[clap(
         long = "min-peers",
         help = "Minimum number of peers the node must have.",
         env = "LIVENESS_CHECKER_MIN_PEERS"
     )]
-    min_peers:     usize,
+    min_peers:         usize,
     #[clap(
         long = "require-baker",
         help = "Require the node to be a baker.",
         env = "LIVENESS_CHECKER_REQUIRE_BAKER"
     )]
-    require_baker: bool,
+    require_baker:     bool,
+    #[clap(
+        long = "progress-interval",
+        help = "If set, query the last finalized block height twice this many seconds apart and \
+                require that it increases.",
+        env = "LIVENESS_CHECKER_PROGRESS_INTERVAL"
+    )]
+    progress_interval: Option<NonZeroU64>,
+    #[clap(
+        long = "require-caught-up",
+        help = "Require that the node is not catching up with any of its peers.",
+        env = "LIVENESS_CHECKER_REQUIRE_CAUGHT_UP"
+    )]
+    require_caught_up: bool,
+    #[clap(
+        long = "serve",
+        help = "Run continuously and serve the result of the checks on this address at /healthz \
+                and /readyz.",
+        env = "LIVENESS_CHECKER_SERVE"
+    )]
+    serve:             Option<SocketAddr>,
+    #[clap(
+        long = "check-interval",
+        help = "Number of seconds between checks when running with --serve.",
+        default_value = "10",
+        env = "LIVENESS_CHECKER_CHECK_INTERVAL"
+    )]
+    check_interval:    NonZeroU64,
 }
 
 #[derive(Debug, Error)]
@@ -59,16 +92,51 @@ enum ReturnStatus {
     TooFewPeers,
     #[error("Not a baker.")]
     NotABaker,
+    #[error("Finalization did not progress.")]
+    FinalizationNotProgressing,
+    #[error("The node is catching up.")]
+    CatchingUp,
+    #[error("Could not run the HTTP server: {0}")]
+    ServerFailed(String),
 }
 
-async fn worker() -> Result<(), ReturnStatus> {
-    let app = App::parse();
+impl ReturnStatus {
+    /// The status code the program exits with.
+    fn exit_code(&self) -> i32 {
+        match self {
+            ReturnStatus::ConnectionFailed(_) => 1,
+            ReturnStatus::RPCError(_) => 2,
+            ReturnStatus::QueryFailed(_) => 3,
+            ReturnStatus::NoFinalization => 4,
+            ReturnStatus::FinalizationTooFarBehind => 5,
+            ReturnStatus::TooFewPeers => 6,
+            ReturnStatus::NotABaker => 7,
+            ReturnStatus::FinalizationNotProgressing => 8,
+            ReturnStatus::CatchingUp => 9,
+            ReturnStatus::ServerFailed(_) => 10,
+        }
+    }
+
+    /// Whether the failure means that the node did not respond to queries, as
+    /// opposed to responding with data that does not satisfy the conditions.
+    fn is_unresponsive(&self) -> bool {
+        matches!(
+            self,
+            ReturnStatus::ConnectionFailed(_)
+                | ReturnStatus::RPCError(_)
+                | ReturnStatus::QueryFailed(_)
+        )
+    }
+}
 
+/// Perform all the configured checks once.
+async fn check(app: &App) -> Result<(), ReturnStatus> {
     let endpoint_with_timeout = app
         .endpoint
+        .clone()
         .connect_timeout(std::time::Duration::from_secs(2))
         .timeout(std::time::Duration::from_secs(5));
-    let mut client = endpoints::Client::connect(endpoint_with_timeout, app.token).await?;
+    let mut client = endpoints::Client::connect(endpoint_with_timeout, app.token.clone()).await?;
 
     let consensus = client.get_consensus_status().await?;
 
@@ -104,6 +172,14 @@ async fn worker() -> Result<(), ReturnStatus> {
         return Err(ReturnStatus::TooFewPeers);
     }
 
+    if app.require_caught_up
+        && peer_list
+            .iter()
+            .any(|peer| matches!(peer.catchup_status, PeerElementCatchupStatus::CatchingUp))
+    {
+        return Err(ReturnStatus::CatchingUp);
+    }
+
     let lfb = consensus.last_finalized_block;
     let lfb_info = client.get_block_info(&lfb).await?;
     if chrono::Utc::now().signed_duration_since(lfb_info.block_slot_time).num_seconds()
@@ -112,41 +188,125 @@ async fn worker() -> Result<(), ReturnStatus> {
         return Err(ReturnStatus::FinalizationTooFarBehind);
     }
 
+    if let Some(interval) = app.progress_interval {
+        tokio::time::sleep(std::time::Duration::from_secs(interval.get())).await;
+        let new_consensus = client.get_consensus_status().await?;
+        if new_consensus.last_finalized_block_height.height
+            <= consensus.last_finalized_block_height.height
+        {
+            return Err(ReturnStatus::FinalizationNotProgressing);
+        }
+    }
+
     Ok(())
 }
 
+/// Outcome of the most recent check in daemon mode.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckResult {
+    /// Time the check completed, [None] if no check has completed yet.
+    checked_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Whether the node responded to the queries.
+    responsive: bool,
+    /// Whether all the checks passed.
+    passed:     bool,
+    /// The status code the checker exits with when run once.
+    code:       Option<i32>,
+    error:      Option<String>,
+}
+
+impl CheckResult {
+    fn new(result: Result<(), ReturnStatus>) -> Self {
+        let checked_at = Some(chrono::Utc::now());
+        match result {
+            Ok(()) => Self {
+                checked_at,
+                responsive: true,
+                passed: true,
+                code: Some(0),
+                error: None,
+            },
+            Err(e) => Self {
+                checked_at,
+                responsive: !e.is_unresponsive(),
+                passed: false,
+                code: Some(e.exit_code()),
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+type SharedResult = Arc<RwLock<CheckResult>>;
+
+/// Responds with 200 if the node responded to the queries in the last check,
+/// regardless of whether the conditions were satisfied.
+async fn healthz(Extension(result): Extension<SharedResult>) -> (StatusCode, Json<CheckResult>) {
+    let result = result.read().await.clone();
+    let status = if result.responsive {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(result))
+}
+
+/// Responds with 200 if all the checks passed in the last check.
+async fn readyz(Extension(result): Extension<SharedResult>) -> (StatusCode, Json<CheckResult>) {
+    let result = result.read().await.clone();
+    let status = if result.passed {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(result))
+}
+
+/// Run the checks every `check_interval` seconds and serve the outcome of the
+/// most recent check over HTTP.
+async fn serve(app: App, addr: SocketAddr) -> Result<(), ReturnStatus> {
+    let result = Arc::new(RwLock::new(CheckResult {
+        checked_at: None,
+        responsive: false,
+        passed:     false,
+        code:       None,
+        error:      Some("No check has completed yet.".into()),
+    }));
+
+    let checker_result = result.clone();
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(app.check_interval.get()));
+        loop {
+            interval.tick().await;
+            let new_result = CheckResult::new(check(&app).await);
+            *checker_result.write().await = new_result;
+        }
+    });
+
+    let router = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .layer(Extension(result));
+
+    axum::Server::try_bind(&addr)
+        .map_err(|e| ReturnStatus::ServerFailed(e.to_string()))?
+        .serve(router.into_make_service())
+        .await
+        .map_err(|e| ReturnStatus::ServerFailed(e.to_string()))
+}
+
 #[tokio::main]
 async fn main() {
-    if let Err(e) = worker().await {
-        match &e {
-            ReturnStatus::ConnectionFailed(_) => {
-                eprintln!("{:?}", e);
-                std::process::exit(1);
-            }
-            ReturnStatus::RPCError(_) => {
-                eprintln!("{:?}", e);
-                std::process::exit(2);
-            }
-            ReturnStatus::QueryFailed(_) => {
-                eprintln!("{:?}", e);
-                std::process::exit(3);
-            }
-            ReturnStatus::NoFinalization => {
-                eprintln!("{:?}", e);
-                std::process::exit(4);
-            }
-            ReturnStatus::FinalizationTooFarBehind => {
-                eprintln!("{:?}", e);
-                std::process::exit(5);
-            }
-            ReturnStatus::TooFewPeers => {
-                eprintln!("{:?}", e);
-                std::process::exit(6);
-            }
-            ReturnStatus::NotABaker => {
-                eprintln!("{:?}", e);
-                std::process::exit(7);
-            }
-        }
+    let app = App::parse();
+
+    let result = match app.serve {
+        Some(addr) => serve(app, addr).await,
+        None => check(&app).await,
+    };
+    if let Err(e) = result {
+        eprintln!("{:?}", e);
+        std::process::exit(e.exit_code());
     }
 }
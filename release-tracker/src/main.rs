@@ -0,0 +1,331 @@
+use anyhow::{ensure, Context};
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use clap::Parser;
+use concordium_rust_sdk::{endpoints, id::types::AccountAddress, types::hashes::BlockHash};
+use prometheus::{Encoder, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+use std::{collections::BTreeMap, net::SocketAddr, num::NonZeroU64, path::PathBuf, sync::Arc};
+use tokio::sync::RwLock;
+
+#[derive(clap::Parser, Debug)]
+#[clap(arg_required_else_help(true))]
+#[clap(version, author)]
+struct App {
+    #[clap(
+        long = "node",
+        help = "GRPC interface of the node.",
+        default_value = "http://localhost:10000",
+        env = "RELEASE_TRACKER_NODE"
+    )]
+    endpoint:       endpoints::Endpoint,
+    #[clap(
+        long = "rpc-token",
+        help = "GRPC interface access token for accessing the node.",
+        default_value = "rpcadmin",
+        env = "RELEASE_TRACKER_TOKEN"
+    )]
+    token:          String,
+    #[clap(
+        long = "accounts",
+        help = "File with the list of accounts to track, one address per line.",
+        env = "RELEASE_TRACKER_ACCOUNTS"
+    )]
+    accounts:       PathBuf,
+    #[clap(
+        long = "listen-address",
+        help = "Address on which to serve the HTTP API and metrics.",
+        default_value = "0.0.0.0:8080",
+        env = "RELEASE_TRACKER_LISTEN_ADDRESS"
+    )]
+    listen_address: SocketAddr,
+    #[clap(
+        long = "windows",
+        help = "Comma separated list of the number of days for which the amount unlocking within \
+                that many days is exported as a metric.",
+        default_value = "1,7,30,90",
+        use_value_delimiter = true,
+        env = "RELEASE_TRACKER_WINDOWS"
+    )]
+    windows:        Vec<u32>,
+    #[clap(
+        long = "poll-interval",
+        help = "Number of seconds between queries of the release schedules.",
+        default_value = "60",
+        env = "RELEASE_TRACKER_POLL_INTERVAL"
+    )]
+    poll_interval:  NonZeroU64,
+}
+
+/// A single scheduled release of an account.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Release {
+    timestamp:        chrono::DateTime<chrono::Utc>,
+    amount_micro_ccd: u64,
+}
+
+/// The release schedules of all tracked accounts as of the last successful
+/// query of the node.
+#[derive(Debug, Default)]
+struct Snapshot {
+    /// The block at which the schedules were queried. [None] until the first
+    /// query has succeeded.
+    block:    Option<BlockHash>,
+    releases: BTreeMap<AccountAddress, Vec<Release>>,
+}
+
+impl Snapshot {
+    /// Amount unlocking for each account within the given number of days
+    /// after `now`. Returns [None] if the end of the period is beyond the
+    /// range of representable times.
+    fn unlocking_within(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+        days: u32,
+    ) -> Option<BTreeMap<AccountAddress, u64>> {
+        let until = now.checked_add_signed(chrono::Duration::days(days.into()))?;
+        let unlocking = self
+            .releases
+            .iter()
+            .map(|(account, releases)| {
+                let amount = releases
+                    .iter()
+                    .filter(|r| r.timestamp > now && r.timestamp <= until)
+                    .map(|r| r.amount_micro_ccd)
+                    .sum();
+                (*account, amount)
+            })
+            .collect();
+        Some(unlocking)
+    }
+}
+
+struct Metrics {
+    registry:    Registry,
+    locked:      IntGaugeVec,
+    unlocking:   IntGaugeVec,
+    last_update: IntGauge,
+}
+
+impl Metrics {
+    fn new() -> anyhow::Result<Self> {
+        let registry = Registry::new();
+        let locked = IntGaugeVec::new(
+            Opts::new(
+                "release_tracker_locked_micro_ccd",
+                "Total amount in scheduled releases of the account.",
+            ),
+            &["account"],
+        )?;
+        let unlocking = IntGaugeVec::new(
+            Opts::new(
+                "release_tracker_unlocking_micro_ccd",
+                "Amount of the account that is released within the given number of days.",
+            ),
+            &["account", "days"],
+        )?;
+        let last_update = IntGauge::new(
+            "release_tracker_last_update_timestamp_seconds",
+            "Time of the last successful query of the release schedules.",
+        )?;
+        registry.register(Box::new(locked.clone()))?;
+        registry.register(Box::new(unlocking.clone()))?;
+        registry.register(Box::new(last_update.clone()))?;
+        Ok(Self {
+            registry,
+            locked,
+            unlocking,
+            last_update,
+        })
+    }
+}
+
+struct State {
+    snapshot: RwLock<Snapshot>,
+    metrics:  Metrics,
+}
+
+/// Read a list of account addresses, one per line. Empty lines are ignored.
+fn read_accounts(path: &std::path::Path) -> anyhow::Result<Vec<AccountAddress>> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Could not read accounts file {}.", path.display()))?;
+    data.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.parse().with_context(|| format!("Invalid account address {}.", line)))
+        .collect()
+}
+
+/// Query the release schedules of all the accounts in the last finalized
+/// block.
+async fn query_snapshot(
+    client: &mut endpoints::Client,
+    accounts: &[AccountAddress],
+) -> anyhow::Result<Snapshot> {
+    let block = client.get_consensus_status().await?.last_finalized_block;
+    let mut releases = BTreeMap::new();
+    for account in accounts {
+        let info = client
+            .get_account_info(account, &block)
+            .await
+            .with_context(|| format!("Could not get account info for {}.", account))?;
+        let schedule = info
+            .account_release_schedule
+            .schedule
+            .into_iter()
+            .map(|r| Release {
+                timestamp:        r.timestamp,
+                amount_micro_ccd: r.amount.microccd,
+            })
+            .collect();
+        releases.insert(*account, schedule);
+    }
+    Ok(Snapshot {
+        block: Some(block),
+        releases,
+    })
+}
+
+/// Update the exported metrics from the snapshot.
+fn update_metrics(metrics: &Metrics, snapshot: &Snapshot, windows: &[u32]) {
+    let now = chrono::Utc::now();
+    for (account, releases) in snapshot.releases.iter() {
+        let total: u64 = releases.iter().map(|r| r.amount_micro_ccd).sum();
+        metrics.locked.with_label_values(&[&account.to_string()]).set(total as i64);
+    }
+    for days in windows {
+        // The windows are validated on startup.
+        for (account, amount) in snapshot.unlocking_within(now, *days).unwrap_or_default() {
+            metrics
+                .unlocking
+                .with_label_values(&[&account.to_string(), &days.to_string()])
+                .set(amount as i64);
+        }
+    }
+    metrics.last_update.set(now.timestamp());
+}
+
+/// Periodically query the node and update the snapshot and metrics. Failed
+/// queries are reported and the previous snapshot is retained.
+async fn poll(
+    mut client: endpoints::Client,
+    accounts: Vec<AccountAddress>,
+    windows: Vec<u32>,
+    interval: std::time::Duration,
+    state: Arc<State>,
+) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        match query_snapshot(&mut client, &accounts).await {
+            Ok(snapshot) => {
+                update_metrics(&state.metrics, &snapshot, &windows);
+                *state.snapshot.write().await = snapshot;
+            }
+            Err(e) => eprintln!("Could not query release schedules: {:#}", e),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct UnlockingParams {
+    days: u32,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UnlockingResponse {
+    block:           BlockHash,
+    days:            u32,
+    total_micro_ccd: u64,
+    accounts:        BTreeMap<AccountAddress, u64>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReleasesResponse {
+    block:    BlockHash,
+    releases: BTreeMap<AccountAddress, Vec<Release>>,
+}
+
+/// Amount unlocking within the requested number of days, per account and in
+/// total.
+async fn unlocking(
+    Query(params): Query<UnlockingParams>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<Json<UnlockingResponse>, StatusCode> {
+    let snapshot = state.snapshot.read().await;
+    let block = snapshot.block.ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let accounts = snapshot
+        .unlocking_within(chrono::Utc::now(), params.days)
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    Ok(Json(UnlockingResponse {
+        block,
+        days: params.days,
+        total_micro_ccd: accounts.values().sum(),
+        accounts,
+    }))
+}
+
+/// All pending releases of the tracked accounts.
+async fn releases(
+    Extension(state): Extension<Arc<State>>,
+) -> Result<Json<ReleasesResponse>, StatusCode> {
+    let snapshot = state.snapshot.read().await;
+    let block = snapshot.block.ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(Json(ReleasesResponse {
+        block,
+        releases: snapshot.releases.clone(),
+    }))
+}
+
+async fn metrics(Extension(state): Extension<Arc<State>>) -> Result<Vec<u8>, StatusCode> {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&state.metrics.registry.gather(), &mut buffer)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(buffer)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let app = App::parse();
+
+    let now = chrono::Utc::now();
+    for days in app.windows.iter() {
+        ensure!(
+            now.checked_add_signed(chrono::Duration::days((*days).into())).is_some(),
+            "The window of {} days is too large.",
+            days
+        );
+    }
+
+    let accounts = read_accounts(&app.accounts)?;
+    let client = endpoints::Client::connect(app.endpoint, app.token).await?;
+
+    let state = Arc::new(State {
+        snapshot: RwLock::new(Snapshot::default()),
+        metrics:  Metrics::new()?,
+    });
+
+    tokio::spawn(poll(
+        client,
+        accounts,
+        app.windows,
+        std::time::Duration::from_secs(app.poll_interval.get()),
+        state.clone(),
+    ));
+
+    let router = Router::new()
+        .route("/unlocking", get(unlocking))
+        .route("/releases", get(releases))
+        .route("/metrics", get(metrics))
+        .layer(Extension(state));
+
+    axum::Server::bind(&app.listen_address).serve(router.into_make_service()).await?;
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use axum::{
+    body::Bytes,
+    extract::{ContentLengthLimit, Extension, Path},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use clap::Parser;
+use concordium_rust_sdk::{
+    endpoints::{self, QueryError},
+    types::smart_contracts::ModuleRef,
+};
+use std::{
+    collections::HashSet,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+mod schema;
+
+/// Maximum size of an uploaded schema in bytes.
+const MAX_SCHEMA_SIZE: u64 = 1024 * 1024;
+
+#[derive(clap::Parser, Debug)]
+#[clap(arg_required_else_help(true))]
+#[clap(version, author)]
+struct App {
+    #[clap(
+        long = "node",
+        help = "GRPC interface of the node.",
+        default_value = "http://localhost:10000",
+        env = "SCHEMA_REGISTRY_NODE"
+    )]
+    endpoint:       endpoints::Endpoint,
+    #[clap(
+        long = "rpc-token",
+        help = "GRPC interface access token for accessing the node.",
+        default_value = "rpcadmin",
+        env = "SCHEMA_REGISTRY_TOKEN"
+    )]
+    token:          String,
+    #[clap(
+        long = "listen-address",
+        help = "Address on which to serve the API.",
+        default_value = "0.0.0.0:8080",
+        env = "SCHEMA_REGISTRY_LISTEN_ADDRESS"
+    )]
+    listen_address: SocketAddr,
+    #[clap(
+        long = "schema-dir",
+        help = "Directory in which schemas are stored.",
+        env = "SCHEMA_REGISTRY_SCHEMA_DIR"
+    )]
+    schema_dir:     PathBuf,
+    #[clap(
+        long = "api-token",
+        help = "Bearer token required for uploading schemas. If not set uploads are disabled.",
+        env = "SCHEMA_REGISTRY_API_TOKEN"
+    )]
+    api_token:      Option<String>,
+}
+
+#[derive(Debug, Error)]
+enum Error {
+    #[error("Invalid module reference.")]
+    InvalidModuleRef,
+    #[error("Uploads are disabled.")]
+    UploadsDisabled,
+    #[error("Missing or invalid API token.")]
+    Unauthorized,
+    #[error("No schema found for the module.")]
+    NotFound,
+    #[error("Could not query the node: {0}")]
+    Query(#[from] QueryError),
+    #[error("Could not parse the module: {0}")]
+    Parse(#[from] schema::ParseError),
+    #[error("Could not access the schema storage: {0}")]
+    Storage(#[from] std::io::Error),
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Error::InvalidModuleRef => StatusCode::BAD_REQUEST,
+            Error::UploadsDisabled => StatusCode::FORBIDDEN,
+            Error::Unauthorized => StatusCode::UNAUTHORIZED,
+            Error::NotFound | Error::Query(QueryError::NotFound) => StatusCode::NOT_FOUND,
+            Error::Query(_) => StatusCode::BAD_GATEWAY,
+            Error::Parse(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+struct State {
+    client:     endpoints::Client,
+    schema_dir: PathBuf,
+    api_token:  Option<String>,
+    /// Counter used to give each write its own temporary file.
+    writes:     AtomicU64,
+    /// Modules deployed on chain without an embedded schema. Modules cannot
+    /// change, so there is no need to look them up again.
+    no_schema:  RwLock<HashSet<ModuleRef>>,
+}
+
+impl State {
+    fn schema_path(&self, module_ref: &ModuleRef) -> PathBuf {
+        self.schema_dir.join(format!("{}.schema", module_ref))
+    }
+
+    /// Write the schema to a new temporary file and return its path. Schemas
+    /// are written to a temporary file first so that readers never see a
+    /// partially written schema. Each write uses its own temporary file so
+    /// that concurrent writes for the same module do not interfere.
+    async fn write_tmp(&self, module_ref: &ModuleRef, schema: &[u8]) -> Result<PathBuf, Error> {
+        let write = self.writes.fetch_add(1, Ordering::Relaxed);
+        let tmp_path = self.schema_path(module_ref).with_extension(format!(
+            "schema.{}.{}.tmp",
+            std::process::id(),
+            write
+        ));
+        tokio::fs::write(&tmp_path, schema).await?;
+        Ok(tmp_path)
+    }
+
+    /// Store the schema, replacing any existing schema for the module.
+    async fn store(&self, module_ref: &ModuleRef, schema: &[u8]) -> Result<(), Error> {
+        let tmp_path = self.write_tmp(module_ref, schema).await?;
+        tokio::fs::rename(&tmp_path, self.schema_path(module_ref)).await?;
+        Ok(())
+    }
+
+    /// Store the schema unless a schema for the module already exists, e.g.,
+    /// because one was uploaded in the meantime. Returns the stored schema.
+    async fn store_if_absent(
+        &self,
+        module_ref: &ModuleRef,
+        schema: Vec<u8>,
+    ) -> Result<Vec<u8>, Error> {
+        let path = self.schema_path(module_ref);
+        let tmp_path = self.write_tmp(module_ref, &schema).await?;
+        // Unlike renaming, linking fails if the target exists.
+        let linked = tokio::fs::hard_link(&tmp_path, &path).await;
+        tokio::fs::remove_file(&tmp_path).await?;
+        match linked {
+            Ok(()) => Ok(schema),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                Ok(tokio::fs::read(&path).await?)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Look up the schema of the module on the chain and store it if it is
+    /// embedded in the module.
+    async fn extract(&self, module_ref: &ModuleRef) -> Result<Vec<u8>, Error> {
+        if self.no_schema.read().await.contains(module_ref) {
+            return Err(Error::NotFound);
+        }
+        let mut client = self.client.clone();
+        let block = client.get_consensus_status().await?.last_finalized_block;
+        let module = client.get_module_source(module_ref, &block).await?;
+        match schema::extract_embedded_schema(module.version, module.source.as_ref())? {
+            Some(schema) => self.store_if_absent(module_ref, schema).await,
+            None => {
+                self.no_schema.write().await.insert(*module_ref);
+                Err(Error::NotFound)
+            }
+        }
+    }
+}
+
+fn parse_module_ref(module_ref: &str) -> Result<ModuleRef, Error> {
+    module_ref.parse().map_err(|_| Error::InvalidModuleRef)
+}
+
+/// Return the schema of the module. If no schema has been uploaded the
+/// schema embedded in the module deployed on chain is returned, if any.
+async fn get_schema(
+    Path(module_ref): Path<String>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<impl IntoResponse, Error> {
+    let module_ref = parse_module_ref(&module_ref)?;
+    let schema = match tokio::fs::read(state.schema_path(&module_ref)).await {
+        Ok(schema) => schema,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => state.extract(&module_ref).await?,
+        Err(e) => return Err(e.into()),
+    };
+    Ok(([(header::CONTENT_TYPE, "application/octet-stream")], schema))
+}
+
+/// Upload the schema of the module, replacing any existing schema.
+async fn post_schema(
+    Path(module_ref): Path<String>,
+    headers: HeaderMap,
+    ContentLengthLimit(schema): ContentLengthLimit<Bytes, MAX_SCHEMA_SIZE>,
+    Extension(state): Extension<Arc<State>>,
+) -> Result<StatusCode, Error> {
+    let api_token = state.api_token.as_ref().ok_or(Error::UploadsDisabled)?;
+    let authorized = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map_or(false, |token| token.as_bytes().ct_eq(api_token.as_bytes()).into());
+    if !authorized {
+        return Err(Error::Unauthorized);
+    }
+    let module_ref = parse_module_ref(&module_ref)?;
+    state.store(&module_ref, &schema).await?;
+    Ok(StatusCode::CREATED)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let app = App::parse();
+
+    tokio::fs::create_dir_all(&app.schema_dir).await?;
+    let client = endpoints::Client::connect(app.endpoint, app.token).await?;
+
+    let state = Arc::new(State {
+        client,
+        schema_dir: app.schema_dir,
+        api_token: app.api_token,
+        writes: AtomicU64::new(0),
+        no_schema: RwLock::new(HashSet::new()),
+    });
+
+    let router = Router::new()
+        .route("/schemas/:module_ref", get(get_schema).post(post_schema))
+        .layer(Extension(state));
+
+    axum::Server::bind(&app.listen_address).serve(router.into_make_service()).await?;
+    Ok(())
+}
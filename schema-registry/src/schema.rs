@@ -0,0 +1,209 @@
+//! Extraction of schemas embedded in the custom sections of Wasm modules.
+
+use concordium_rust_sdk::types::smart_contracts::WasmVersion;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("The module does not start with the Wasm magic and version.")]
+    InvalidHeader,
+    #[error("The module ends unexpectedly.")]
+    UnexpectedEnd,
+    #[error("Invalid LEB128 encoded integer.")]
+    InvalidInteger,
+}
+
+/// Name of the custom section containing the embedded schema. Modules of
+/// version 0 use the `v1` schema format, modules of version 1 use the `v2`
+/// format. Newer tooling embeds a versioned schema in the `concordium-schema`
+/// section, which takes precedence if present.
+fn section_names(version: WasmVersion) -> [&'static str; 2] {
+    match version {
+        WasmVersion::V0 => ["concordium-schema", "concordium-schema-v1"],
+        WasmVersion::V1 => ["concordium-schema", "concordium-schema-v2"],
+    }
+}
+
+/// A cursor over the bytes of a module.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        if self.bytes.len() < n {
+            return Err(ParseError::UnexpectedEnd);
+        }
+        let (start, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(start)
+    }
+
+    fn byte(&mut self) -> Result<u8, ParseError> { Ok(self.take(1)?[0]) }
+
+    /// Read an unsigned LEB128 encoded 32-bit integer.
+    fn u32(&mut self) -> Result<u32, ParseError> {
+        let mut result: u32 = 0;
+        for i in 0..5 {
+            let b = self.byte()?;
+            let value = u32::from(b & 0x7f);
+            if i == 4 && value > 0x0f {
+                return Err(ParseError::InvalidInteger);
+            }
+            result |= value << (7 * i);
+            if b & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+        Err(ParseError::InvalidInteger)
+    }
+}
+
+/// Look up the embedded schema of the module source, if any. The module is
+/// only parsed as far as needed to find the custom sections.
+pub fn extract_embedded_schema(
+    version: WasmVersion,
+    source: &[u8],
+) -> Result<Option<Vec<u8>>, ParseError> {
+    let mut cursor = Cursor {
+        bytes: source,
+    };
+    if cursor.take(8).map_err(|_| ParseError::InvalidHeader)? != b"\0asm\x01\0\0\0" {
+        return Err(ParseError::InvalidHeader);
+    }
+    let names = section_names(version);
+    let mut found: [Option<&[u8]>; 2] = [None, None];
+    while !cursor.bytes.is_empty() {
+        let id = cursor.byte()?;
+        let size = cursor.u32()? as usize;
+        let contents = cursor.take(size)?;
+        if id != 0 {
+            continue;
+        }
+        let mut section = Cursor {
+            bytes: contents,
+        };
+        let name_len = section.u32()? as usize;
+        let name = section.take(name_len)?;
+        for (name_candidate, slot) in names.iter().zip(found.iter_mut()) {
+            if name == name_candidate.as_bytes() && slot.is_none() {
+                *slot = Some(section.bytes);
+            }
+        }
+    }
+    Ok(found.iter().flatten().next().map(|schema| schema.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &[u8] = b"\0asm\x01\0\0\0";
+
+    /// A module consisting of the header followed by the given sections.
+    fn module(sections: &[Vec<u8>]) -> Vec<u8> {
+        let mut module = HEADER.to_vec();
+        for section in sections {
+            module.extend_from_slice(section);
+        }
+        module
+    }
+
+    /// A section whose contents are shorter than 128 bytes, so that the size
+    /// is encoded in a single byte.
+    fn section(id: u8, contents: &[u8]) -> Vec<u8> {
+        let mut section = vec![id, contents.len() as u8];
+        section.extend_from_slice(contents);
+        section
+    }
+
+    fn custom_section(name: &str, contents: &[u8]) -> Vec<u8> {
+        let mut data = vec![name.len() as u8];
+        data.extend_from_slice(name.as_bytes());
+        data.extend_from_slice(contents);
+        section(0, &data)
+    }
+
+    #[test]
+    fn invalid_header() {
+        assert!(matches!(
+            extract_embedded_schema(WasmVersion::V0, b"\0asm\x02\0\0\0"),
+            Err(ParseError::InvalidHeader)
+        ));
+        assert!(matches!(
+            extract_embedded_schema(WasmVersion::V0, b"\0asm"),
+            Err(ParseError::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn truncated_section() {
+        let mut source = module(&[custom_section("concordium-schema-v1", b"schema")]);
+        source.pop();
+        assert!(matches!(
+            extract_embedded_schema(WasmVersion::V0, &source),
+            Err(ParseError::UnexpectedEnd)
+        ));
+    }
+
+    #[test]
+    fn overlong_integer() {
+        // The fifth byte of a 32-bit integer may only use the lower four bits.
+        let source = module(&[vec![0, 0x80, 0x80, 0x80, 0x80, 0x10]]);
+        assert!(matches!(
+            extract_embedded_schema(WasmVersion::V0, &source),
+            Err(ParseError::InvalidInteger)
+        ));
+        // An integer may be at most five bytes long.
+        let source = module(&[vec![0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]]);
+        assert!(matches!(
+            extract_embedded_schema(WasmVersion::V0, &source),
+            Err(ParseError::InvalidInteger)
+        ));
+    }
+
+    #[test]
+    fn versioned_schema_takes_precedence() {
+        let source = module(&[
+            custom_section("concordium-schema-v1", b"v1"),
+            custom_section("concordium-schema", b"versioned"),
+        ]);
+        assert_eq!(
+            extract_embedded_schema(WasmVersion::V0, &source).unwrap(),
+            Some(b"versioned".to_vec())
+        );
+        let source = module(&[
+            custom_section("concordium-schema", b"versioned"),
+            custom_section("concordium-schema-v2", b"v2"),
+        ]);
+        assert_eq!(
+            extract_embedded_schema(WasmVersion::V1, &source).unwrap(),
+            Some(b"versioned".to_vec())
+        );
+    }
+
+    #[test]
+    fn schema_for_module_version() {
+        let source = module(&[
+            custom_section("concordium-schema-v1", b"v1"),
+            custom_section("concordium-schema-v2", b"v2"),
+        ]);
+        assert_eq!(
+            extract_embedded_schema(WasmVersion::V0, &source).unwrap(),
+            Some(b"v1".to_vec())
+        );
+        assert_eq!(
+            extract_embedded_schema(WasmVersion::V1, &source).unwrap(),
+            Some(b"v2".to_vec())
+        );
+    }
+
+    #[test]
+    fn no_custom_section() {
+        assert_eq!(extract_embedded_schema(WasmVersion::V0, HEADER).unwrap(), None);
+        let source = module(&[section(1, &[0])]);
+        assert_eq!(extract_embedded_schema(WasmVersion::V0, &source).unwrap(), None);
+        let source = module(&[section(1, &[0]), custom_section("name", b"")]);
+        assert_eq!(extract_embedded_schema(WasmVersion::V1, &source).unwrap(), None);
+    }
+}